@@ -1,50 +1,167 @@
+mod pedal_devices;
+mod pedal_gesture;
+mod pedal_idle;
+mod pedal_mapping;
+mod pedal_state;
+
+use pedal_devices::SupportedPedal;
+use pedal_gesture::{GestureConfig, GestureTracker};
+use pedal_idle::IdleWatchdog;
+use pedal_mapping::{PedalButton, PedalEdge, PedalMapping};
+use pedal_state::{PedalState, PedalStateMessage};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{Emitter, Manager, State};
 
-// --- VEC Infinity 3 USB Identifiers ---
-const VEC_VENDOR_ID: u16 = 0x05f3;
-const VEC_PRODUCT_ID: u16 = 0x00ff;
+// How long we'll wait for `open_path` to come back before giving up on this
+// attempt and going back to searching.
+const OPEN_TIMEOUT: Duration = Duration::from_secs(3);
+
+// --- Shared state for the pedal connection state machine ---
+#[derive(Default)]
+struct SharedPedalState(Arc<Mutex<PedalState>>);
 
-// --- Shared State to track pedal connection ---
+// --- Shared state for the configurable button mapping ---
 #[derive(Default)]
-struct PedalConnectionState(Arc<Mutex<bool>>);
+struct MappingState(Arc<Mutex<PedalMapping>>);
+
+// --- Shared state for the configurable gesture timing thresholds ---
+#[derive(Default)]
+struct GestureConfigState(Arc<Mutex<GestureConfig>>);
 
-// --- Tauri Command for the frontend to query the state ---
 #[tauri::command]
-fn is_pedal_connected(state: State<'_, PedalConnectionState>) -> bool {
+fn pedal_state(state: State<'_, SharedPedalState>) -> PedalState {
     *state.0.lock().unwrap()
 }
 
+#[tauri::command]
+fn get_pedal_mapping(state: State<'_, MappingState>) -> PedalMapping {
+    state.0.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_pedal_mapping(
+    app: tauri::AppHandle,
+    state: State<'_, MappingState>,
+    mapping: PedalMapping,
+) -> Result<(), String> {
+    pedal_mapping::save_mapping(&app, &mapping)?;
+    *state.0.lock().unwrap() = mapping;
+    Ok(())
+}
+
+#[tauri::command]
+fn set_pedal_binding(
+    app: tauri::AppHandle,
+    state: State<'_, MappingState>,
+    button: PedalButton,
+    edge: PedalEdge,
+    action: String,
+) -> Result<(), String> {
+    let mut mapping = state.0.lock().unwrap();
+    mapping.set_key(button, edge, action);
+    pedal_mapping::save_mapping(&app, &mapping)
+}
+
+#[tauri::command]
+fn delete_pedal_binding(
+    app: tauri::AppHandle,
+    state: State<'_, MappingState>,
+    button: PedalButton,
+    edge: PedalEdge,
+) -> Result<(), String> {
+    let mut mapping = state.0.lock().unwrap();
+    mapping.del_key(button, edge);
+    pedal_mapping::save_mapping(&app, &mapping)
+}
+
+#[tauri::command]
+fn get_gesture_config(state: State<'_, GestureConfigState>) -> GestureConfig {
+    *state.0.lock().unwrap()
+}
+
+#[tauri::command]
+fn set_gesture_config(state: State<'_, GestureConfigState>, config: GestureConfig) {
+    *state.0.lock().unwrap() = config;
+}
+
+#[tauri::command]
+fn set_idle_timeout(watchdog: State<'_, IdleWatchdog>, ms: u64) {
+    watchdog.set_timeout(Duration::from_millis(ms));
+}
+
+#[tauri::command]
+fn reset_idle_timer(watchdog: State<'_, IdleWatchdog>) {
+    watchdog.reset();
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .manage(PedalConnectionState::default()) // Add the state to Tauri
-        .invoke_handler(tauri::generate_handler![is_pedal_connected]) // Register the command
+        .manage(SharedPedalState::default())
+        .manage(MappingState::default())
+        .manage(GestureConfigState::default())
+        .manage(IdleWatchdog::default())
+        .invoke_handler(tauri::generate_handler![
+            pedal_state,
+            get_pedal_mapping,
+            set_pedal_mapping,
+            set_pedal_binding,
+            delete_pedal_binding,
+            get_gesture_config,
+            set_gesture_config,
+            set_idle_timeout,
+            reset_idle_timer
+        ])
         .setup(|app| {
             let handle = app.handle().clone();
-            let state = app.state::<PedalConnectionState>();
-            let state_clone = state.0.clone();
+
+            let shared_state = app.state::<SharedPedalState>();
+            let tx = pedal_state::spawn(handle.clone(), shared_state.0.clone());
+
+            // Load the persisted mapping (or fall back to the default layout) now
+            // that the app config dir is resolvable.
+            let mapping_state = app.state::<MappingState>();
+            *mapping_state.0.lock().unwrap() = pedal_mapping::load_mapping(&handle);
+            let mapping_clone = mapping_state.0.clone();
+
+            let gesture_config_state = app.state::<GestureConfigState>();
+            let gesture_config_clone = gesture_config_state.0.clone();
+
+            let idle_watchdog = app.state::<IdleWatchdog>().inner().clone();
+            idle_watchdog.spawn_watchdog(handle.clone());
 
             // Spawn a background thread to handle HID communication
+            let action_handle = handle.clone();
+            let open_in_flight = Arc::new(AtomicBool::new(false));
             thread::spawn(move || {
                 loop {
+                    let _ = tx.send(PedalStateMessage::Scanning);
                     if let Ok(api) = hidapi::HidApi::new() {
-                        if let Some(device_info) = api.device_list().find(|d| {
-                            d.vendor_id() == VEC_VENDOR_ID && d.product_id() == VEC_PRODUCT_ID
-                        }) {
-                            println!("VEC Infinity 3 pedal found!");
-                            // Update shared state and emit event
-                            *state_clone.lock().unwrap() = true;
-                            let _ = handle.emit("pedal-found", true);
-
-                            if let Ok(device) = device_info.open_device(&api) {
-                                poll_pedal(&device, &handle);
-                                // If poll_pedal exits, it means the pedal was disconnected
-                                *state_clone.lock().unwrap() = false;
-                                let _ = handle.emit("pedal-disconnected", true);
-                                println!("Pedal disconnected. Will try to reconnect...");
+                        if let Some((device_info, pedal)) =
+                            pedal_devices::find_supported(api.device_list())
+                        {
+                            match open_with_timeout(device_info.path(), OPEN_TIMEOUT, &open_in_flight) {
+                                Some(device) => {
+                                    let _ = tx.send(PedalStateMessage::DeviceFound(pedal.name.to_string()));
+                                    poll_pedal(
+                                        &device,
+                                        pedal,
+                                        &action_handle,
+                                        &tx,
+                                        &mapping_clone,
+                                        &gesture_config_clone,
+                                        &idle_watchdog,
+                                    );
+                                    // If poll_pedal exits, the pedal was disconnected or errored.
+                                    let _ = tx.send(PedalStateMessage::DeviceLost);
+                                }
+                                None => {
+                                    let _ = tx.send(PedalStateMessage::CommandTimeout);
+                                }
                             }
                         }
                     }
@@ -58,49 +175,107 @@ pub fn run() {
         .expect("error while running tauri application");
 }
 
+/// Opens the device at `path` on a helper thread so a stalled `open_path` call
+/// can't block the poll loop forever; gives up after `timeout`.
+///
+/// `in_flight` caps us at one outstanding helper thread: if a previous attempt
+/// is still stuck in the real `open_path` syscall when the outer loop retries,
+/// we skip spawning another one rather than abandoning a fresh thread on top
+/// of it every 5 seconds.
+fn open_with_timeout(
+    path: &std::ffi::CStr,
+    timeout: Duration,
+    in_flight: &Arc<AtomicBool>,
+) -> Option<hidapi::HidDevice> {
+    if in_flight.swap(true, Ordering::SeqCst) {
+        return None;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let path = path.to_owned();
+    let in_flight = in_flight.clone();
+    thread::spawn(move || {
+        let device = hidapi::HidApi::new().and_then(|api| api.open_path(&path)).ok();
+        in_flight.store(false, Ordering::SeqCst);
+        let _ = tx.send(device);
+    });
+    rx.recv_timeout(timeout).unwrap_or(None)
+}
+
 // This function runs in a loop, reading the pedal's state and emitting events
-fn poll_pedal(device: &hidapi::HidDevice, handle: &tauri::AppHandle) {
+fn poll_pedal(
+    device: &hidapi::HidDevice,
+    pedal: &SupportedPedal,
+    handle: &tauri::AppHandle,
+    tx: &Sender<PedalStateMessage>,
+    mapping: &Arc<Mutex<PedalMapping>>,
+    gesture_config: &Arc<Mutex<GestureConfig>>,
+    idle_watchdog: &IdleWatchdog,
+) {
     let mut last_state = 0u8;
     let mut buf = [0u8; 8]; // Buffer to read HID report
+    let mut gestures = GestureTracker::default();
 
     loop {
         match device.read_timeout(&mut buf, 100) {
             Ok(_) => {
-                let current_state = buf[0];
+                let now = Instant::now();
+                let config = *gesture_config.lock().unwrap();
+                let current_state = (pedal.decode)(&buf);
                 if current_state != last_state {
                     // 1 = Left, 2 = Center, 4 = Right
-                    if (current_state & 1) != (last_state & 1) {
-                        let event = if (current_state & 1) > 0 {
-                            "left-pressed"
-                        } else {
-                            "left-released"
-                        };
-                        let _ = handle.emit("pedal-action", event);
-                    }
-                    if (current_state & 2) != (last_state & 2) {
-                        let event = if (current_state & 2) > 0 {
-                            "center-pressed"
-                        } else {
-                            "center-released"
-                        };
-                        let _ = handle.emit("pedal-action", event);
-                    }
-                    if (current_state & 4) != (last_state & 4) {
-                        let event = if (current_state & 4) > 0 {
-                            "right-pressed"
-                        } else {
-                            "right-released"
-                        };
-                        let _ = handle.emit("pedal-action", event);
-                    }
+                    emit_edge(handle, mapping, &mut gestures, &config, idle_watchdog, PedalButton::Left, 1, current_state, last_state, now);
+                    emit_edge(handle, mapping, &mut gestures, &config, idle_watchdog, PedalButton::Center, 2, current_state, last_state, now);
+                    emit_edge(handle, mapping, &mut gestures, &config, idle_watchdog, PedalButton::Right, 4, current_state, last_state, now);
                     println!("Pedal state changed to: {}", current_state);
                     last_state = current_state;
                 }
+                // `read_timeout` returns every 100ms regardless of change; use
+                // each tick to notice a still-held long-press or an expired
+                // double-tap window.
+                for (button, gesture) in gestures.on_idle(&config, now) {
+                    emit_gesture(handle, button, gesture);
+                }
             }
             Err(e) => {
                 eprintln!("Error reading from HID device: {}. Assuming disconnect.", e);
+                let _ = tx.send(PedalStateMessage::PollError(e.to_string()));
                 break; // Exit the polling loop to allow reconnection
             }
         }
     }
 }
+
+/// Translates a single button's bit flag into its configured logical action (if
+/// bound), emits it as a `pedal-action` event, and feeds the edge into gesture
+/// detection.
+#[allow(clippy::too_many_arguments)]
+fn emit_edge(
+    handle: &tauri::AppHandle,
+    mapping: &Arc<Mutex<PedalMapping>>,
+    gestures: &mut GestureTracker,
+    gesture_config: &GestureConfig,
+    idle_watchdog: &IdleWatchdog,
+    button: PedalButton,
+    bit: u8,
+    current_state: u8,
+    last_state: u8,
+    now: Instant,
+) {
+    if (current_state & bit) == (last_state & bit) {
+        return;
+    }
+    let pressed = (current_state & bit) > 0;
+    let edge = if pressed { PedalEdge::Press } else { PedalEdge::Release };
+    if let Some(action) = mapping.lock().unwrap().action_for(button, edge) {
+        let _ = handle.emit("pedal-action", action.to_string());
+        idle_watchdog.record_activity(handle);
+    }
+    if let Some(gesture) = gestures.on_edge(button, pressed, gesture_config, now) {
+        emit_gesture(handle, button, gesture);
+    }
+}
+
+fn emit_gesture(handle: &tauri::AppHandle, button: PedalButton, gesture: pedal_gesture::Gesture) {
+    let _ = handle.emit("pedal-gesture", format!("{}-{}", button.as_str(), gesture.event_suffix()));
+}
@@ -0,0 +1,88 @@
+/// A USB HID foot pedal this app knows how to talk to. Supporting a new model
+/// is a matter of adding an entry here rather than touching the poll loop.
+pub struct SupportedPedal {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    /// Restricts matching to one HID interface when a device exposes several
+    /// (e.g. a pedal that also enumerates as a keyboard).
+    pub interface_number: Option<i32>,
+    pub name: &'static str,
+    /// Decodes a raw HID input report into our canonical button bitmask
+    /// (bit0 = left, bit1 = center, bit2 = right), since the bit layout isn't
+    /// the same across pedal models.
+    pub decode: fn(&[u8]) -> u8,
+}
+
+fn decode_vec_infinity3(report: &[u8]) -> u8 {
+    // Already reports 1 = Left, 2 = Center, 4 = Right - matches our canonical
+    // bitmask as-is.
+    report[0]
+}
+
+impl SupportedPedal {
+    /// Whether this entry's vendor/product id and (if set) interface number
+    /// filter match the given device identity.
+    fn matches(&self, vendor_id: u16, product_id: u16, interface_number: i32) -> bool {
+        self.vendor_id == vendor_id
+            && self.product_id == product_id
+            && self.interface_number.is_none_or(|n| n == interface_number)
+    }
+}
+
+pub const SUPPORTED_PEDALS: &[SupportedPedal] = &[SupportedPedal {
+    vendor_id: 0x05f3,
+    product_id: 0x00ff,
+    interface_number: None,
+    name: "VEC Infinity 3",
+    decode: decode_vec_infinity3,
+}];
+
+/// Finds the first registered pedal model present in `device_list`, honoring
+/// each entry's `interface_number` filter when set.
+pub fn find_supported<'a>(
+    mut device_list: impl Iterator<Item = &'a hidapi::DeviceInfo>,
+) -> Option<(&'a hidapi::DeviceInfo, &'static SupportedPedal)> {
+    device_list.find_map(|info| {
+        SUPPORTED_PEDALS
+            .iter()
+            .find(|pedal| pedal.matches(info.vendor_id(), info.product_id(), info.interface_number()))
+            .map(|pedal| (info, pedal))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pedal(interface_number: Option<i32>) -> SupportedPedal {
+        SupportedPedal {
+            vendor_id: 0x05f3,
+            product_id: 0x00ff,
+            interface_number,
+            name: "Test Pedal",
+            decode: decode_vec_infinity3,
+        }
+    }
+
+    #[test]
+    fn rejects_a_mismatched_vendor_or_product_id() {
+        let pedal = pedal(None);
+        assert!(!pedal.matches(0x0001, 0x00ff, 0));
+        assert!(!pedal.matches(0x05f3, 0x0001, 0));
+    }
+
+    #[test]
+    fn no_interface_filter_matches_any_interface() {
+        let pedal = pedal(None);
+        assert!(pedal.matches(0x05f3, 0x00ff, 0));
+        assert!(pedal.matches(0x05f3, 0x00ff, 3));
+    }
+
+    #[test]
+    fn interface_filter_rejects_every_other_interface() {
+        let pedal = pedal(Some(1));
+        assert!(pedal.matches(0x05f3, 0x00ff, 1));
+        assert!(!pedal.matches(0x05f3, 0x00ff, 0));
+        assert!(!pedal.matches(0x05f3, 0x00ff, 2));
+    }
+}
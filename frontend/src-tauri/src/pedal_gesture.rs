@@ -0,0 +1,241 @@
+use crate::pedal_mapping::PedalButton;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Timing thresholds for gesture detection, tunable per-session via the
+/// `get_gesture_config` / `set_gesture_config` commands.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GestureConfig {
+    /// How long a button must be held before it counts as a long-press rather
+    /// than a tap.
+    pub long_press_ms: u64,
+    /// How long after a tap we'll wait for a second one before emitting it as
+    /// a single tap.
+    pub double_tap_window_ms: u64,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            long_press_ms: 600,
+            double_tap_window_ms: 300,
+        }
+    }
+}
+
+/// A recognized gesture on a button, independent of the raw press/release
+/// edges that make it up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    Tap,
+    DoubleTap,
+    LongPress,
+}
+
+impl Gesture {
+    pub fn event_suffix(self) -> &'static str {
+        match self {
+            Gesture::Tap => "tap",
+            Gesture::DoubleTap => "double-tap",
+            Gesture::LongPress => "long-press",
+        }
+    }
+}
+
+#[derive(Default)]
+struct ButtonTracker {
+    pressed_at: Option<Instant>,
+    long_press_fired: bool,
+    pending_tap_at: Option<Instant>,
+}
+
+/// Tracks press/release timing per button to turn raw edges into taps,
+/// double-taps, and long-presses.
+///
+/// `on_edge` handles completed presses (a release arrives); `on_idle` must be
+/// called on every poll tick even when the raw state hasn't changed, since
+/// `read_timeout` already returns every 100ms and that's the only chance to
+/// notice a still-held long-press or an expired double-tap window.
+#[derive(Default)]
+pub struct GestureTracker {
+    left: ButtonTracker,
+    center: ButtonTracker,
+    right: ButtonTracker,
+}
+
+impl GestureTracker {
+    fn tracker_mut(&mut self, button: PedalButton) -> &mut ButtonTracker {
+        match button {
+            PedalButton::Left => &mut self.left,
+            PedalButton::Center => &mut self.center,
+            PedalButton::Right => &mut self.right,
+        }
+    }
+
+    /// Feed a press (`pressed = true`) or release (`pressed = false`) edge.
+    /// Returns the gesture that edge completed, if any.
+    pub fn on_edge(
+        &mut self,
+        button: PedalButton,
+        pressed: bool,
+        config: &GestureConfig,
+        now: Instant,
+    ) -> Option<Gesture> {
+        let tracker = self.tracker_mut(button);
+        if pressed {
+            tracker.pressed_at = Some(now);
+            tracker.long_press_fired = false;
+            return None;
+        }
+
+        let pressed_at = tracker.pressed_at.take()?;
+        if tracker.long_press_fired {
+            // Already reported as a long-press while held; the release doesn't
+            // also complete a tap.
+            tracker.long_press_fired = false;
+            return None;
+        }
+
+        if now.duration_since(pressed_at) >= Duration::from_millis(config.long_press_ms) {
+            return Some(Gesture::LongPress);
+        }
+
+        if let Some(first_tap_at) = tracker.pending_tap_at.take() {
+            if now.duration_since(first_tap_at) <= Duration::from_millis(config.double_tap_window_ms) {
+                return Some(Gesture::DoubleTap);
+            }
+        }
+        tracker.pending_tap_at = Some(now);
+        None
+    }
+
+    /// Call on every poll tick to surface gestures that complete by the
+    /// passage of time rather than by an edge: a long-press while still held,
+    /// or a single tap whose double-tap window just expired.
+    pub fn on_idle(&mut self, config: &GestureConfig, now: Instant) -> Vec<(PedalButton, Gesture)> {
+        let mut events = Vec::new();
+        for (button, tracker) in [
+            (PedalButton::Left, &mut self.left),
+            (PedalButton::Center, &mut self.center),
+            (PedalButton::Right, &mut self.right),
+        ] {
+            if let Some(pressed_at) = tracker.pressed_at {
+                if !tracker.long_press_fired
+                    && now.duration_since(pressed_at) >= Duration::from_millis(config.long_press_ms)
+                {
+                    tracker.long_press_fired = true;
+                    events.push((button, Gesture::LongPress));
+                }
+            }
+            if let Some(first_tap_at) = tracker.pending_tap_at {
+                if now.duration_since(first_tap_at) > Duration::from_millis(config.double_tap_window_ms) {
+                    tracker.pending_tap_at = None;
+                    events.push((button, Gesture::Tap));
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> GestureConfig {
+        GestureConfig {
+            long_press_ms: 50,
+            double_tap_window_ms: 60,
+        }
+    }
+
+    #[test]
+    fn quick_release_does_not_complete_a_gesture_immediately() {
+        let mut tracker = GestureTracker::default();
+        let config = config();
+        let press_at = Instant::now();
+        assert_eq!(tracker.on_edge(PedalButton::Left, true, &config, press_at), None);
+        let release_at = press_at + Duration::from_millis(10);
+        assert_eq!(tracker.on_edge(PedalButton::Left, false, &config, release_at), None);
+    }
+
+    #[test]
+    fn tap_emitted_once_the_double_tap_window_expires() {
+        let mut tracker = GestureTracker::default();
+        let config = config();
+        let press_at = Instant::now();
+        tracker.on_edge(PedalButton::Left, true, &config, press_at);
+        let release_at = press_at + Duration::from_millis(10);
+        tracker.on_edge(PedalButton::Left, false, &config, release_at);
+
+        let still_in_window = release_at + Duration::from_millis(config.double_tap_window_ms - 5);
+        assert_eq!(tracker.on_idle(&config, still_in_window), vec![]);
+
+        let after_window = release_at + Duration::from_millis(config.double_tap_window_ms + 5);
+        assert_eq!(tracker.on_idle(&config, after_window), vec![(PedalButton::Left, Gesture::Tap)]);
+    }
+
+    #[test]
+    fn second_tap_within_window_is_a_double_tap() {
+        let mut tracker = GestureTracker::default();
+        let config = config();
+        let first_press = Instant::now();
+        tracker.on_edge(PedalButton::Center, true, &config, first_press);
+        let first_release = first_press + Duration::from_millis(10);
+        tracker.on_edge(PedalButton::Center, false, &config, first_release);
+
+        let second_press = first_release + Duration::from_millis(config.double_tap_window_ms - 10);
+        tracker.on_edge(PedalButton::Center, true, &config, second_press);
+        let second_release = second_press + Duration::from_millis(10);
+        let gesture = tracker.on_edge(PedalButton::Center, false, &config, second_release);
+
+        assert_eq!(gesture, Some(Gesture::DoubleTap));
+    }
+
+    #[test]
+    fn second_tap_after_window_expires_starts_a_new_tap_instead() {
+        let mut tracker = GestureTracker::default();
+        let config = config();
+        let first_press = Instant::now();
+        tracker.on_edge(PedalButton::Center, true, &config, first_press);
+        let first_release = first_press + Duration::from_millis(10);
+        tracker.on_edge(PedalButton::Center, false, &config, first_release);
+
+        let second_press = first_release + Duration::from_millis(config.double_tap_window_ms + 10);
+        tracker.on_edge(PedalButton::Center, true, &config, second_press);
+        let second_release = second_press + Duration::from_millis(10);
+        let gesture = tracker.on_edge(PedalButton::Center, false, &config, second_release);
+
+        assert_eq!(gesture, None);
+    }
+
+    #[test]
+    fn held_past_threshold_is_a_long_press_on_release() {
+        let mut tracker = GestureTracker::default();
+        let config = config();
+        let press_at = Instant::now();
+        tracker.on_edge(PedalButton::Right, true, &config, press_at);
+        let release_at = press_at + Duration::from_millis(config.long_press_ms + 5);
+        let gesture = tracker.on_edge(PedalButton::Right, false, &config, release_at);
+        assert_eq!(gesture, Some(Gesture::LongPress));
+    }
+
+    #[test]
+    fn long_press_fires_exactly_once_while_still_held() {
+        let mut tracker = GestureTracker::default();
+        let config = config();
+        let press_at = Instant::now();
+        tracker.on_edge(PedalButton::Right, true, &config, press_at);
+
+        let past_threshold = press_at + Duration::from_millis(config.long_press_ms + 5);
+        assert_eq!(tracker.on_idle(&config, past_threshold), vec![(PedalButton::Right, Gesture::LongPress)]);
+
+        // Still held on the next tick - shouldn't re-fire.
+        let later = past_threshold + Duration::from_millis(5);
+        assert_eq!(tracker.on_idle(&config, later), vec![]);
+
+        // Releasing after a long-press was already reported doesn't also emit a tap.
+        let release_at = later + Duration::from_millis(5);
+        assert_eq!(tracker.on_edge(PedalButton::Right, false, &config, release_at), None);
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watches for pedal inactivity during a transcription session and emits
+/// `idle-timeout` / `activity-resumed` so the frontend can auto-pause and
+/// resume. Cheap to clone: every clone shares the same underlying clock.
+#[derive(Clone)]
+pub struct IdleWatchdog {
+    last_activity: Arc<Mutex<Instant>>,
+    timeout: Arc<Mutex<Duration>>,
+    idle_fired: Arc<Mutex<bool>>,
+}
+
+impl Default for IdleWatchdog {
+    fn default() -> Self {
+        Self {
+            last_activity: Arc::new(Mutex::new(Instant::now())),
+            timeout: Arc::new(Mutex::new(DEFAULT_IDLE_TIMEOUT)),
+            idle_fired: Arc::new(Mutex::new(false)),
+        }
+    }
+}
+
+impl IdleWatchdog {
+    /// Resets the idle clock. Returns `true` if the watchdog had already
+    /// fired `idle-timeout`, meaning the caller should emit
+    /// `activity-resumed`; kept separate from `record_activity` so the latch
+    /// behavior is testable without an `AppHandle`.
+    fn mark_activity(&self) -> bool {
+        *self.last_activity.lock().unwrap() = Instant::now();
+        let mut fired = self.idle_fired.lock().unwrap();
+        if *fired {
+            *fired = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Resets the idle clock, as if called from a `pedal-action` emission. If
+    /// the watchdog had already fired `idle-timeout`, also emits
+    /// `activity-resumed`.
+    pub fn record_activity(&self, handle: &tauri::AppHandle) {
+        if self.mark_activity() {
+            let _ = handle.emit("activity-resumed", true);
+        }
+    }
+
+    /// Resets the idle clock without implying resumed pedal activity. Lets the
+    /// frontend keep a legitimately running session (audio still playing,
+    /// pedal untouched) from tripping the watchdog.
+    pub fn reset(&self) {
+        *self.last_activity.lock().unwrap() = Instant::now();
+    }
+
+    pub fn set_timeout(&self, timeout: Duration) {
+        *self.timeout.lock().unwrap() = timeout;
+    }
+
+    /// Spawns the background thread that polls the idle clock and emits
+    /// `idle-timeout` once, the moment the threshold is crossed.
+    pub fn spawn_watchdog(&self, handle: tauri::AppHandle) {
+        let watchdog = self.clone();
+        thread::spawn(move || loop {
+            thread::sleep(CHECK_INTERVAL);
+            let timeout = *watchdog.timeout.lock().unwrap();
+            let idle_for = watchdog.last_activity.lock().unwrap().elapsed();
+            let mut fired = watchdog.idle_fired.lock().unwrap();
+            if should_fire(idle_for, timeout, *fired) {
+                *fired = true;
+                let _ = handle.emit("idle-timeout", true);
+            }
+        });
+    }
+}
+
+/// Whether the idle threshold has been crossed and `idle-timeout` hasn't
+/// already fired for this idle stretch - kept separate from the watchdog's
+/// locking so the crossing logic itself is trivial to unit test.
+fn should_fire(idle_for: Duration, timeout: Duration, already_fired: bool) -> bool {
+    !already_fired && idle_for >= timeout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_fire_before_the_timeout_elapses() {
+        assert!(!should_fire(Duration::from_millis(90), Duration::from_millis(100), false));
+    }
+
+    #[test]
+    fn fires_once_the_timeout_elapses() {
+        assert!(should_fire(Duration::from_millis(100), Duration::from_millis(100), false));
+    }
+
+    #[test]
+    fn does_not_re_fire_if_already_fired() {
+        assert!(!should_fire(Duration::from_millis(200), Duration::from_millis(100), true));
+    }
+
+    #[test]
+    fn record_activity_reports_resumed_only_if_idle_had_fired() {
+        let watchdog = IdleWatchdog::default();
+        assert!(!watchdog.mark_activity());
+
+        *watchdog.idle_fired.lock().unwrap() = true;
+        assert!(watchdog.mark_activity());
+        assert!(!*watchdog.idle_fired.lock().unwrap());
+    }
+
+    #[test]
+    fn record_activity_is_a_no_op_report_when_not_idle() {
+        let watchdog = IdleWatchdog::default();
+        assert!(!watchdog.mark_activity());
+        assert!(!*watchdog.idle_fired.lock().unwrap());
+    }
+
+    #[test]
+    fn reset_does_not_clear_the_idle_fired_latch() {
+        let watchdog = IdleWatchdog::default();
+        *watchdog.idle_fired.lock().unwrap() = true;
+        watchdog.reset();
+        assert!(*watchdog.idle_fired.lock().unwrap());
+    }
+}
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::Manager;
+
+/// The three physical buttons on the pedal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PedalButton {
+    Left,
+    Center,
+    Right,
+}
+
+impl PedalButton {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PedalButton::Left => "left",
+            PedalButton::Center => "center",
+            PedalButton::Right => "right",
+        }
+    }
+}
+
+/// Whether a binding fires on press or on release of a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PedalEdge {
+    Press,
+    Release,
+}
+
+/// A logical transcription action, e.g. `"play"`, `"rewind-5s"`, `"insert-timestamp"`.
+pub type PedalAction = String;
+
+/// Maps each (button, edge) combination to the logical action it should emit.
+///
+/// Persisted as JSON under the Tauri app config dir so it survives restarts and
+/// can be edited by hand if needed. `schema_version` records which round of
+/// [`DEFAULT_BINDINGS`] this profile has already been offered, so loading an
+/// older file can backfill bindings introduced since without resurrecting ones
+/// the user deliberately removed with `del_key`. Absent in files saved before
+/// versioning existed, which `serde` defaults to `0`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PedalMapping {
+    #[serde(default)]
+    schema_version: u32,
+    bindings: HashMap<String, PedalAction>,
+}
+
+impl PedalMapping {
+    fn key(button: PedalButton, edge: PedalEdge) -> String {
+        format!("{button:?}-{edge:?}").to_lowercase()
+    }
+
+    /// Looks up the logical action bound to a button edge, if any.
+    pub fn action_for(&self, button: PedalButton, edge: PedalEdge) -> Option<&str> {
+        self.bindings.get(&Self::key(button, edge)).map(String::as_str)
+    }
+
+    /// Binds a button edge to a logical action, overwriting any existing binding.
+    pub fn set_key(&mut self, button: PedalButton, edge: PedalEdge, action: PedalAction) {
+        self.bindings.insert(Self::key(button, edge), action);
+    }
+
+    /// Adds a binding only if the edge is not already bound.
+    pub fn append_key(&mut self, button: PedalButton, edge: PedalEdge, action: PedalAction) {
+        self.bindings.entry(Self::key(button, edge)).or_insert(action);
+    }
+
+    /// Removes the binding for a button edge, if any.
+    pub fn del_key(&mut self, button: PedalButton, edge: PedalEdge) {
+        self.bindings.remove(&Self::key(button, edge));
+    }
+}
+
+/// The current schema version. Bump this and give a new `DEFAULT_BINDINGS`
+/// entry that version when shipping an additional default action, so profiles
+/// saved before it exist get it backfilled on next load.
+const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// The stock VEC Infinity 3 layout: left rewinds, center plays/pauses, right
+/// drops a timestamp. The last field is the schema version each binding was
+/// introduced in.
+const DEFAULT_BINDINGS: &[(PedalButton, PedalEdge, &str, u32)] = &[
+    (PedalButton::Left, PedalEdge::Press, "rewind-5s", 1),
+    (PedalButton::Center, PedalEdge::Press, "play", 1),
+    (PedalButton::Center, PedalEdge::Release, "pause", 1),
+    (PedalButton::Right, PedalEdge::Press, "insert-timestamp", 1),
+];
+
+impl Default for PedalMapping {
+    fn default() -> Self {
+        let mut mapping = Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            bindings: HashMap::new(),
+        };
+        for (button, edge, action, _) in DEFAULT_BINDINGS {
+            mapping.set_key(*button, *edge, (*action).to_string());
+        }
+        mapping
+    }
+}
+
+/// Adds any default binding introduced in a schema version newer than
+/// `mapping`'s, then stamps `mapping` as caught up to [`CURRENT_SCHEMA_VERSION`].
+///
+/// Only bindings introduced *after* a profile was last saved get backfilled:
+/// a profile can't have deliberately deleted a binding it never had the
+/// chance to see, but it may well have deleted one that was already part of
+/// its own schema version, and that deletion must stick.
+fn backfill_new_defaults(mapping: &mut PedalMapping) {
+    for (button, edge, action, introduced_in) in DEFAULT_BINDINGS {
+        if *introduced_in > mapping.schema_version {
+            mapping.append_key(*button, *edge, (*action).to_string());
+        }
+    }
+    mapping.schema_version = CURRENT_SCHEMA_VERSION;
+}
+
+fn config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("could not resolve app config dir: {e}"))?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join("pedal_mapping.json"))
+}
+
+/// Loads the persisted mapping, falling back to [`PedalMapping::default`] if no
+/// config file exists yet or it fails to parse. Otherwise backfills only the
+/// default bindings introduced since this profile's schema version - see
+/// [`backfill_new_defaults`] for why a plain "fill in whatever's missing"
+/// would silently undo `del_key`.
+pub fn load_mapping(app: &tauri::AppHandle) -> PedalMapping {
+    let loaded = config_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok());
+    let Some(mut mapping) = loaded else {
+        return PedalMapping::default();
+    };
+    backfill_new_defaults(&mut mapping);
+    mapping
+}
+
+/// Writes the mapping to the app config dir as pretty-printed JSON, stamped
+/// with the current schema version so a later load doesn't re-offer defaults
+/// this save already had the chance to keep or delete.
+pub fn save_mapping(app: &tauri::AppHandle, mapping: &PedalMapping) -> Result<(), String> {
+    let path = config_path(app)?;
+    let mut to_write = mapping.clone();
+    to_write.schema_version = CURRENT_SCHEMA_VERSION;
+    let raw = serde_json::to_string_pretty(&to_write).map_err(|e| e.to_string())?;
+    fs::write(path, raw).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_mapping_matches_shipped_layout() {
+        let mapping = PedalMapping::default();
+        assert_eq!(mapping.action_for(PedalButton::Left, PedalEdge::Press), Some("rewind-5s"));
+        assert_eq!(mapping.action_for(PedalButton::Center, PedalEdge::Press), Some("play"));
+        assert_eq!(mapping.action_for(PedalButton::Center, PedalEdge::Release), Some("pause"));
+        assert_eq!(mapping.action_for(PedalButton::Right, PedalEdge::Press), Some("insert-timestamp"));
+        assert_eq!(mapping.action_for(PedalButton::Right, PedalEdge::Release), None);
+    }
+
+    #[test]
+    fn set_key_overwrites_existing_binding() {
+        let mut mapping = PedalMapping::default();
+        mapping.set_key(PedalButton::Left, PedalEdge::Press, "rewind-15s".into());
+        assert_eq!(mapping.action_for(PedalButton::Left, PedalEdge::Press), Some("rewind-15s"));
+    }
+
+    #[test]
+    fn append_key_does_not_overwrite_an_existing_binding() {
+        let mut mapping = PedalMapping::default();
+        mapping.append_key(PedalButton::Left, PedalEdge::Press, "rewind-15s".into());
+        assert_eq!(mapping.action_for(PedalButton::Left, PedalEdge::Press), Some("rewind-5s"));
+    }
+
+    #[test]
+    fn append_key_fills_a_missing_binding() {
+        let mut mapping = PedalMapping::default();
+        mapping.del_key(PedalButton::Right, PedalEdge::Press);
+        mapping.append_key(PedalButton::Right, PedalEdge::Press, "insert-timestamp".into());
+        assert_eq!(mapping.action_for(PedalButton::Right, PedalEdge::Press), Some("insert-timestamp"));
+    }
+
+    #[test]
+    fn del_key_removes_a_binding() {
+        let mut mapping = PedalMapping::default();
+        mapping.del_key(PedalButton::Center, PedalEdge::Press);
+        assert_eq!(mapping.action_for(PedalButton::Center, PedalEdge::Press), None);
+    }
+
+    #[test]
+    fn json_round_trip_preserves_bindings() {
+        let mut mapping = PedalMapping::default();
+        mapping.set_key(PedalButton::Left, PedalEdge::Release, "rewind-30s".into());
+        let raw = serde_json::to_string(&mapping).expect("serialize");
+        let restored: PedalMapping = serde_json::from_str(&raw).expect("deserialize");
+        assert_eq!(restored.action_for(PedalButton::Left, PedalEdge::Release), Some("rewind-30s"));
+        assert_eq!(restored.action_for(PedalButton::Center, PedalEdge::Press), Some("play"));
+    }
+
+    #[test]
+    fn backfill_does_not_resurrect_a_deliberately_deleted_default() {
+        let mut mapping = PedalMapping::default();
+        mapping.del_key(PedalButton::Right, PedalEdge::Press);
+        backfill_new_defaults(&mut mapping);
+        assert_eq!(mapping.action_for(PedalButton::Right, PedalEdge::Press), None);
+    }
+
+    #[test]
+    fn backfill_adds_bindings_introduced_after_this_profile_was_saved() {
+        let mut mapping = PedalMapping {
+            schema_version: 0,
+            bindings: HashMap::new(),
+        };
+        backfill_new_defaults(&mut mapping);
+        assert_eq!(mapping.action_for(PedalButton::Right, PedalEdge::Press), Some("insert-timestamp"));
+        assert_eq!(mapping.schema_version, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn missing_schema_version_in_json_defaults_to_zero() {
+        let restored: PedalMapping = serde_json::from_str(r#"{"bindings": {}}"#).expect("deserialize");
+        assert_eq!(restored.schema_version, 0);
+    }
+}
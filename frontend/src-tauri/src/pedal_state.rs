@@ -0,0 +1,115 @@
+use serde::Serialize;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::Emitter;
+
+/// High-level connection state of the pedal, mirrored to the frontend via the
+/// `pedal-state` event and the `pedal_state` command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PedalState {
+    #[default]
+    Disconnected,
+    Searching,
+    Connected,
+    PollError,
+}
+
+/// Messages the background HID thread sends to the central state handler. The
+/// handler is the only thing that ever mutates [`PedalState`], so the HID
+/// thread never touches shared state directly.
+#[derive(Debug)]
+pub enum PedalStateMessage {
+    /// A scan of `device_list()` started with no device matched yet.
+    Scanning,
+    /// A registered pedal model was matched and successfully opened; carries
+    /// the model's human-readable name.
+    DeviceFound(String),
+    /// The previously connected device stopped responding or was unplugged.
+    DeviceLost,
+    /// A read call returned an error.
+    PollError(String),
+    /// The device was matched but opening it didn't succeed in time; treated
+    /// the same as never having found it rather than left hanging.
+    CommandTimeout,
+}
+
+/// Maps a message to the connection state it transitions to, independent of
+/// the event emission that accompanies the transition - kept separate so the
+/// mapping is testable without a live `AppHandle`.
+fn next_state(message: &PedalStateMessage) -> PedalState {
+    match message {
+        PedalStateMessage::Scanning => PedalState::Searching,
+        PedalStateMessage::DeviceFound(_) => PedalState::Connected,
+        PedalStateMessage::DeviceLost => PedalState::Searching,
+        PedalStateMessage::PollError(_) => PedalState::PollError,
+        PedalStateMessage::CommandTimeout => PedalState::Searching,
+    }
+}
+
+/// Spawns the central state handler thread and returns the sender the
+/// background HID thread uses to report progress.
+pub fn spawn(handle: tauri::AppHandle, state: Arc<Mutex<PedalState>>) -> mpsc::Sender<PedalStateMessage> {
+    let (tx, rx) = mpsc::channel::<PedalStateMessage>();
+
+    thread::spawn(move || {
+        for message in rx {
+            set_state(&handle, &state, next_state(&message));
+            match message {
+                PedalStateMessage::Scanning => {}
+                PedalStateMessage::DeviceFound(model_name) => {
+                    let _ = handle.emit("pedal-found", model_name);
+                }
+                PedalStateMessage::DeviceLost => {
+                    let _ = handle.emit("pedal-disconnected", true);
+                }
+                PedalStateMessage::PollError(reason) => {
+                    let _ = handle.emit("pedal-poll-error", reason);
+                }
+                PedalStateMessage::CommandTimeout => {
+                    let _ = handle.emit("pedal-command-timeout", true);
+                }
+            }
+        }
+    });
+
+    tx
+}
+
+fn set_state(handle: &tauri::AppHandle, state: &Arc<Mutex<PedalState>>, next: PedalState) {
+    *state.lock().unwrap() = next;
+    let _ = handle.emit("pedal-state", next);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scanning_transitions_to_searching() {
+        assert_eq!(next_state(&PedalStateMessage::Scanning), PedalState::Searching);
+    }
+
+    #[test]
+    fn device_found_transitions_to_connected() {
+        let message = PedalStateMessage::DeviceFound("VEC Infinity 3".to_string());
+        assert_eq!(next_state(&message), PedalState::Connected);
+    }
+
+    #[test]
+    fn device_lost_transitions_back_to_searching() {
+        assert_eq!(next_state(&PedalStateMessage::DeviceLost), PedalState::Searching);
+    }
+
+    #[test]
+    fn poll_error_transitions_to_poll_error_state() {
+        let message = PedalStateMessage::PollError("read failed".to_string());
+        assert_eq!(next_state(&message), PedalState::PollError);
+    }
+
+    #[test]
+    fn command_timeout_transitions_back_to_searching_rather_than_left_pending() {
+        assert_eq!(next_state(&PedalStateMessage::CommandTimeout), PedalState::Searching);
+    }
+}